@@ -1,11 +1,17 @@
+use std::collections::HashMap;
 use std::fmt;
 use std::process::{Command, Stdio};
-use std::fs::File;
-use std::io::{BufReader, Read};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use thiserror::Error as ThisError;
-use sha2::{Sha256, Digest};
+use sha2::{Sha256, Sha512, Digest};
+use sha3::Sha3_256;
 use strict_yaml_rust::{StrictYaml as Yaml};
 
 use crate::error::{Error, Result};
@@ -16,6 +22,129 @@ pub trait FromYaml: Sized {
 
 type Sha = String;
 
+/// Size of the leading/trailing block sampled per file in [`HashMode::Partial`].
+const PARTIAL_BLOCK: u64 = 4096;
+
+/// How much of each watched file feeds into the entry's digest.
+///
+/// `Full` streams every byte (the authoritative default); `Partial` folds in
+/// only the file length plus its first and last [`PARTIAL_BLOCK`] bytes, a
+/// cheap signature for multi-gigabyte inputs. A matching signature counts as
+/// fresh; the full hash is computed only when the signature differs, so an
+/// edit that keeps a file's length and both sampled blocks is not detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashMode {
+    Full,
+    Partial,
+}
+
+impl Default for HashMode {
+    fn default() -> Self {
+        HashMode::Full
+    }
+}
+
+/// Digest algorithm used to hash an entry's inputs.
+///
+/// The chosen algorithm is recorded as a `"<algo>:<hexdigest>"` prefix on the
+/// dumped `sha`, so the on-disk value is self-describing and a manifest can
+/// mix algorithms per entry. A bare, prefix-less digest is read back as
+/// [`Algo::Sha256`] for backwards compatibility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algo {
+    Sha256,
+    Sha512,
+    Sha3_256,
+    Blake3,
+}
+
+impl Default for Algo {
+    fn default() -> Self {
+        Algo::Sha256
+    }
+}
+
+impl Algo {
+    fn tag(self) -> &'static str {
+        match self {
+            Algo::Sha256 => "sha256",
+            Algo::Sha512 => "sha512",
+            Algo::Sha3_256 => "sha3-256",
+            Algo::Blake3 => "blake3",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "sha256" => Some(Algo::Sha256),
+            "sha512" => Some(Algo::Sha512),
+            "sha3-256" => Some(Algo::Sha3_256),
+            "blake3" => Some(Algo::Blake3),
+            _ => None,
+        }
+    }
+}
+
+/// Split a recorded `sha` into its algorithm and bare hex digest, defaulting
+/// to [`Algo::Sha256`] when no known `"<algo>:"` prefix is present so that
+/// manifests written before this field keep verifying.
+fn parse_sha(sha: &str) -> (Algo, &str) {
+    match sha.split_once(':') {
+        Some((tag, hex)) => match Algo::from_tag(tag) {
+            Some(algo) => (algo, hex),
+            None => (Algo::Sha256, sha),
+        },
+        None => (Algo::Sha256, sha),
+    }
+}
+
+/// Algorithm-dispatched digest accumulator, so the content- and partial-hash
+/// passes can share one streaming loop across every supported [`Algo`].
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Sha3_256(Sha3_256),
+    Blake3(blake3::Hasher),
+}
+
+impl Hasher {
+    fn new(algo: Algo) -> Self {
+        match algo {
+            Algo::Sha256 => Hasher::Sha256(Sha256::new()),
+            Algo::Sha512 => Hasher::Sha512(Sha512::new()),
+            Algo::Sha3_256 => Hasher::Sha3_256(Sha3_256::new()),
+            Algo::Blake3 => Hasher::Blake3(blake3::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+            Hasher::Sha3_256(h) => h.update(data),
+            Hasher::Blake3(h) => { h.update(data); },
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Sha256(h) => format!("{:x}", h.finalize()),
+            Hasher::Sha512(h) => format!("{:x}", h.finalize()),
+            Hasher::Sha3_256(h) => format!("{:x}", h.finalize()),
+            Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+/// Recorded `(len, mtime)` of a watched file, used to skip hashing when
+/// nothing has moved since the last dump.
+#[derive(Debug, Clone)]
+struct FileStamp {
+    file: String,
+    mtime: u128,
+    len: u64,
+}
+
 #[derive(Debug)]
 pub enum ReifySuccess {
     ExecSuccess(Sha),
@@ -30,10 +159,54 @@ pub enum ReifyFail {
     MissingRequiredFiles,
     #[error("dry run, things have changed")]
     DryFail,
+    #[error("skipped, upstream producer failed")]
+    UpstreamFail,
 }
 
 pub type ReifyResult = core::result::Result<ReifySuccess, ReifyFail>;
 
+/// Error returned by [`reify_all`] when the entries cannot be scheduled.
+#[derive(ThisError, Debug)]
+pub enum ScheduleError {
+    #[error("dependency cycle among entries: {0}")]
+    Cycle(String),
+}
+
+/// Drift state of a single entry as reported by [`Entry::verify`], without
+/// ever running its `cmd`.
+#[derive(Debug)]
+pub enum VerifyStatus {
+    /// The recorded sha still matches the current inputs.
+    Ok,
+    /// Content changed since the last reify; lists the watched files whose
+    /// recorded `(len, mtime)` no longer match (or that appeared/disappeared).
+    Stale { changed: Vec<String> },
+    /// One or more required patterns resolve to no existing file.
+    Missing { absent: Vec<String> },
+}
+
+impl VerifyStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            VerifyStatus::Ok => "OK",
+            VerifyStatus::Stale { .. } => "STALE",
+            VerifyStatus::Missing { .. } => "MISSING",
+        }
+    }
+
+    /// Whether this status represents drift, i.e. should fail a CI gate.
+    pub fn is_drift(&self) -> bool {
+        !matches!(self, VerifyStatus::Ok)
+    }
+}
+
+/// Per-entry result of a [`verify_all`] walk.
+#[derive(Debug)]
+pub struct VerifyReport {
+    pub name: String,
+    pub status: VerifyStatus,
+}
+
 #[derive(Debug)]
 pub struct Entry {
     name: String,
@@ -41,10 +214,92 @@ pub struct Entry {
     required_files: Vec<String>,
     files: Vec<String>,
     sha: Option<String>,
+    psha: Option<String>,
+    cmd_sha: Option<String>,
+    hash_mode: HashMode,
+    algo: Algo,
+    mtimes: Vec<FileStamp>,
+}
+
+/// Expand a single `files`/`required_files` pattern into the canonicalized,
+/// deterministically sorted set of regular files it names.
+///
+/// A pattern containing glob metacharacters is matched with [`glob`]; a plain
+/// path that points at a directory is walked recursively; anything else is
+/// treated as a literal file. Non-existent paths and glob patterns that match
+/// nothing expand to the empty set, which is how the `MissingRequiredFiles`
+/// check and the content hash both notice files appearing or disappearing.
+fn expand(pattern: &str) -> Vec<PathBuf> {
+    let mut out = if pattern.contains(['*', '?', '[']) {
+        match glob::glob(pattern) {
+            Ok(paths) => paths
+                .filter_map(core::result::Result::ok)
+                .filter(|p| p.is_file())
+                .filter_map(|p| p.canonicalize().ok())
+                .collect::<Vec<_>>(),
+            Err(_) => Vec::new(),
+        }
+    } else {
+        let path = Path::new(pattern);
+        if path.is_dir() {
+            walk_dir(path)
+        } else {
+            path.canonicalize().ok().into_iter().filter(|p| p.is_file()).collect()
+        }
+    };
+    out.sort();
+    out
+}
+
+/// Recursively collect the canonicalized regular files beneath `dir`.
+fn walk_dir(dir: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                out.extend(walk_dir(&path));
+            } else if let Ok(canon) = path.canonicalize() {
+                if canon.is_file() {
+                    out.push(canon);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Best-effort absolute path for dependency matching. Unlike a bare
+/// [`Path::canonicalize`] this does not require the file to exist yet, so a producer's not-yet-built
+/// `files` still line up with a consumer's `required_files`.
+fn abspath(p: &str) -> PathBuf {
+    match Path::new(p).canonicalize() {
+        Ok(c) => c,
+        Err(_) => match std::env::current_dir() {
+            Ok(cwd) => cwd.join(p),
+            Err(_) => PathBuf::from(p),
+        },
+    }
 }
 
-fn canonicalize(p: &String) -> Option<PathBuf> {
-    Path::new(p).canonicalize().ok()
+fn stamp_vec(y: &Yaml) -> Vec<FileStamp> {
+    match y {
+        Yaml::Array(x) => x.iter()
+            .filter_map(|rec| Some(FileStamp {
+                file: rec["file"].as_str().map(String::from)?,
+                mtime: rec["mtime"].as_str()?.parse().ok()?,
+                len: rec["len"].as_str()?.parse().ok()?,
+            }))
+            .collect::<Vec<_>>(),
+        _ => vec![],
+    }
+}
+
+fn mtime_nanos(meta: &fs::Metadata) -> u128 {
+    meta.modified().ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
 }
 
 fn str_vec(y: &Yaml) -> Vec<String> {
@@ -58,17 +313,94 @@ fn str_vec(y: &Yaml) -> Vec<String> {
     }
 }
 
+/// The file set an entry's patterns resolve to for a single reify/verify pass.
+///
+/// Computed once per pass by [`Entry::resolve`] and threaded through the hash,
+/// stamp and missing-files paths so a dir/glob watch is walked exactly once —
+/// both to keep the common path O(files) and to close the TOCTOU window where
+/// separate walks could disagree if files change mid-reify. The one exception
+/// is the sha recorded after a successful `cmd`, which is taken over a fresh
+/// resolve because `cmd` may create or remove watched files.
+struct Resolved {
+    /// Canonicalized, deterministically sorted union of all watched files.
+    files: Vec<PathBuf>,
+    /// Raw `required_files` patterns that resolved to no existing file.
+    absent: Vec<String>,
+}
+
 impl Entry {
-    fn calc_sha(&self) -> Result<Sha> {
+    /// Expand every `files`/`required_files` pattern once, collecting the
+    /// deduplicated file set that feeds the sha and noting any required
+    /// pattern that matched nothing.
+    fn resolve(&self) -> Resolved {
+        let mut files = Vec::new();
+        for p in self.files.iter() {
+            files.extend(expand(p));
+        }
+        let mut absent = Vec::new();
+        for p in self.required_files.iter() {
+            let matched = expand(p);
+            if matched.is_empty() {
+                absent.push(p.clone());
+            }
+            files.extend(matched);
+        }
+        files.sort();
+        files.dedup();
+        Resolved { files, absent }
+    }
+
+    /// Current `(len, mtime)` stamps for the resolved files, as written to
+    /// the dumped `mtimes:` list.
+    fn calc_mtimes(&self, files: &[PathBuf]) -> Vec<FileStamp> {
+        files.iter()
+            .filter_map(|path| fs::metadata(path).ok().map(|meta| FileStamp {
+                file: path.to_string_lossy().into_owned(),
+                mtime: mtime_nanos(&meta),
+                len: meta.len(),
+            }))
+            .collect()
+    }
+
+    /// Sha-256 of `cmd`, stamped alongside `mtimes:` so the fast path can tell
+    /// that the command itself changed even when every watched file is
+    /// untouched.
+    fn cmd_digest(&self) -> String {
         let mut hasher = Sha256::new();
+        hasher.update(self.cmd.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Whether every watched file still matches its recorded `(len, mtime)`
+    /// and `cmd` is unchanged. A cheap, O(files) pre-check that lets
+    /// `check_then` skip hashing entirely when nothing has moved. A missing
+    /// `cmd` stamp, a `cmd` edit, missing or extra files, or any mismatch all
+    /// fall back to the authoritative content hash (which also folds `cmd`).
+    fn mtimes_match(&self, files: &[PathBuf]) -> bool {
+        if self.cmd_sha.as_deref() != Some(self.cmd_digest().as_str()) {
+            return false;
+        }
+        if self.mtimes.is_empty() || files.len() != self.mtimes.len() {
+            return false;
+        }
+        files.iter().all(|path| {
+            let key = path.to_string_lossy();
+            match self.mtimes.iter().find(|s| s.file == key) {
+                Some(stamp) => match fs::metadata(path) {
+                    Ok(meta) => stamp.len == meta.len() && stamp.mtime == mtime_nanos(&meta),
+                    Err(_) => false,
+                },
+                None => false,
+            }
+        })
+    }
+
+    /// Full content digest under `algo`, tagged as `"<algo>:<hexdigest>"`.
+    fn calc_sha(&self, algo: Algo, files: &[PathBuf]) -> Result<Sha> {
+        let mut hasher = Hasher::new(algo);
         let mut buffer = [0; 1024];
-        let mut all_files = self.files.iter()
-            .chain(self.required_files.iter())
-            .filter_map(canonicalize)
-            .collect::<Vec<_>>();
-        all_files.sort();
-        for file in all_files {
-            let input = File::open(&file)?;
+        for file in files {
+            let input = File::open(file)?;
             let mut reader = BufReader::new(input);
 
             loop {
@@ -77,8 +409,34 @@ impl Entry {
                 hasher.update(&buffer[..count]);
             }
         }
-        hasher.update(&self.cmd);
-        Ok(format!("{:x}", hasher.finalize()))
+        hasher.update(self.cmd.as_bytes());
+        Ok(format!("{}:{}", algo.tag(), hasher.finalize()))
+    }
+
+    /// Cheap per-file signature: the file length plus its first and last
+    /// [`PARTIAL_BLOCK`] bytes, folded together with `cmd`. Used as a fast
+    /// first comparison in [`HashMode::Partial`]: a match is taken as fresh
+    /// without a full hash, and only a mismatch falls through to the full
+    /// content hash.
+    fn calc_partial(&self, algo: Algo, files: &[PathBuf]) -> Result<Sha> {
+        let mut hasher = Hasher::new(algo);
+        let mut buffer = [0; PARTIAL_BLOCK as usize];
+        for file in files {
+            let mut input = File::open(file)?;
+            let len = input.metadata()?.len();
+            hasher.update(&len.to_le_bytes());
+
+            let count = input.read(&mut buffer)?;
+            hasher.update(&buffer[..count]);
+
+            if len > PARTIAL_BLOCK {
+                input.seek(SeekFrom::Start(len.saturating_sub(PARTIAL_BLOCK)))?;
+                let count = input.read(&mut buffer)?;
+                hasher.update(&buffer[..count]);
+            }
+        }
+        hasher.update(self.cmd.as_bytes());
+        Ok(format!("{}:{}", algo.tag(), hasher.finalize()))
     }
 
     fn exec(&self) -> Result<i32> {
@@ -97,12 +455,36 @@ impl Entry {
         }
     }
 
-    fn check_then<F>(&self, exec: F) -> Result<ReifyResult>
+    fn check_then<F>(&self, files: &[PathBuf], exec: F) -> Result<ReifyResult>
     where F: FnOnce() -> Result<ReifyResult> {
         if let Some(old_sha) = self.sha.as_ref() {
-            // Check if existing sha matches newly calculated one
-            let new_sha = self.calc_sha()?;
-            if &new_sha != old_sha {
+            // Fast path: if every watched file still matches its recorded
+            // (len, mtime) we can treat the entry as fresh without reading
+            // a byte. The content hash below remains authoritative, so a
+            // stamp miss only costs us the full pass we would have paid
+            // anyway.
+            if self.mtimes_match(files) {
+                return Ok(Ok(ReifySuccess::Noop));
+            }
+            // In Partial mode compare the cheap signature first; a match is
+            // treated as fresh and only a miss forces the full content pass,
+            // which then decides whether to exec.
+            if self.hash_mode == HashMode::Partial {
+                if let Some(old_psha) = self.psha.as_ref() {
+                    let (algo, old_hex) = parse_sha(old_psha);
+                    let new_psha = self.calc_partial(algo, files)?;
+                    let (_, new_hex) = parse_sha(&new_psha);
+                    if new_hex == old_hex {
+                        return Ok(Ok(ReifySuccess::Noop));
+                    }
+                }
+            }
+            // Check if existing sha matches newly calculated one, hashing
+            // with whatever algorithm the recorded sha was tagged with.
+            let (algo, old_hex) = parse_sha(old_sha);
+            let new_sha = self.calc_sha(algo, files)?;
+            let (_, new_hex) = parse_sha(&new_sha);
+            if new_hex != old_hex {
                 // If shas don't match execute entry and re-calculate sha
                 exec()
             } else {
@@ -116,29 +498,88 @@ impl Entry {
     }
 
     pub fn reify(&self) -> Result<ReifyResult> {
+        let resolved = self.resolve();
+        let files = &resolved.files;
         let exec = || self.exec()
             .and_then(|code| {
                 if code == 0 {
-                    self.calc_sha()
+                    self.calc_sha(self.algo, &self.resolve().files)
                         .and_then(|sha| Ok(Ok(ReifySuccess::ExecSuccess(sha))))
                 } else {
                     Ok(Err(ReifyFail::ExecFail(code)))
                 }
             });
 
-        let len = self.required_files.iter().filter_map(canonicalize).collect::<Vec<_>>().len();
-        if  self.required_files.len() == len {
-            self.check_then(exec)
+        // Every required pattern must expand to at least one existing file;
+        // a glob matching nothing or a missing literal path is a miss.
+        if resolved.absent.is_empty() {
+            self.check_then(files, exec)
         } else {
             Ok(Err(ReifyFail::MissingRequiredFiles))
         }
     }
 
     pub fn dry_run(&self) -> Result<ReifyResult> {
-        self.check_then(|| Ok(Err(ReifyFail::DryFail)))
+        let resolved = self.resolve();
+        self.check_then(&resolved.files, || Ok(Err(ReifyFail::DryFail)))
+    }
+
+    /// Watched files whose current `(len, mtime)` differ from what was
+    /// recorded at the last dump — the human-readable detail behind a
+    /// `STALE` verdict. Falls back to listing every watched file when no
+    /// stamps were recorded (e.g. an entry that has never been reified).
+    fn changed_files(&self, files: &[PathBuf]) -> Vec<String> {
+        let current = self.calc_mtimes(files);
+        if self.mtimes.is_empty() {
+            return current.into_iter().map(|s| s.file).collect();
+        }
+        let mut changed = Vec::new();
+        for cur in current.iter() {
+            match self.mtimes.iter().find(|s| s.file == cur.file) {
+                Some(rec) if rec.len == cur.len && rec.mtime == cur.mtime => {}
+                _ => changed.push(cur.file.clone()),
+            }
+        }
+        for rec in self.mtimes.iter() {
+            if !current.iter().any(|c| c.file == rec.file) {
+                changed.push(rec.file.clone());
+            }
+        }
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+
+    /// Report the entry's drift without executing it: `MISSING` if a required
+    /// pattern resolves to nothing, `STALE` if no sha is recorded or the
+    /// content hash no longer matches it, otherwise `OK`.
+    pub fn verify(&self) -> VerifyReport {
+        let resolved = self.resolve();
+        if !resolved.absent.is_empty() {
+            return VerifyReport {
+                name: self.name.clone(),
+                status: VerifyStatus::Missing { absent: resolved.absent },
+            };
+        }
+
+        let fresh = match self.sha.as_ref() {
+            Some(old_sha) => {
+                let (algo, old_hex) = parse_sha(old_sha);
+                self.calc_sha(algo, &resolved.files).map(|new| parse_sha(&new).1 == old_hex).unwrap_or(false)
+            }
+            None => false,
+        };
+
+        let status = if fresh {
+            VerifyStatus::Ok
+        } else {
+            VerifyStatus::Stale { changed: self.changed_files(&resolved.files) }
+        };
+        VerifyReport { name: self.name.clone(), status }
     }
 
     pub fn dump(&self, w: &mut dyn fmt::Write, new_sha: Option<Sha>) -> Result<()> {
+        let resolved = self.resolve();
         writeln!(w ,"-")?;
 
         if self.name != "" {
@@ -164,9 +605,34 @@ impl Entry {
             }
         }
 
+        if self.algo != Algo::Sha256 {
+            writeln!(w ,"  algo: {}", self.algo.tag())?;
+        }
+
+        if self.hash_mode == HashMode::Partial {
+            writeln!(w ,"  hash_mode: partial")?;
+        }
+
         if let Some(sha) = new_sha.or_else(|| self.sha.clone()) {
             writeln!(w ,"  sha: {}", sha)?;
         }
+
+        if self.hash_mode == HashMode::Partial {
+            if let Some(psha) = self.calc_partial(self.algo, &resolved.files).ok().or_else(|| self.psha.clone()) {
+                writeln!(w ,"  psha: {}", psha)?;
+            }
+        }
+
+        let mtimes = self.calc_mtimes(&resolved.files);
+        if ! mtimes.is_empty() {
+            writeln!(w ,"  cmd_sha: {}", self.cmd_digest())?;
+            writeln!(w ,"  mtimes:")?;
+            for stamp in mtimes.iter() {
+                writeln!(w ,"  - file: {}", stamp.file)?;
+                writeln!(w ,"    mtime: {}", stamp.mtime)?;
+                writeln!(w ,"    len: {}", stamp.len)?;
+            }
+        }
         Ok(())
     }
 
@@ -175,6 +641,195 @@ impl Entry {
     }
 }
 
+/// Verify every entry without executing any `cmd`, returning one report per
+/// entry in input order. The tree is considered clean only when every report
+/// is [`VerifyStatus::Ok`] — see [`reports_have_drift`] for the CI-gate check.
+pub fn verify_all(entries: &[Entry]) -> Vec<VerifyReport> {
+    entries.iter().map(Entry::verify).collect()
+}
+
+/// Whether any report in a [`verify_all`] walk represents drift, i.e. the
+/// process should exit nonzero.
+pub fn reports_have_drift(reports: &[VerifyReport]) -> bool {
+    reports.iter().any(|r| r.status.is_drift())
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render `verify_all` reports as a one-line-per-entry text summary, mirroring
+/// the `OK`/`STALE`/`MISSING` register of a checksum tool's `--check` output.
+pub fn write_report_text(w: &mut dyn fmt::Write, reports: &[VerifyReport]) -> Result<()> {
+    for report in reports.iter() {
+        writeln!(w, "{}: {}", report.status.label(), report.name)?;
+        match &report.status {
+            VerifyStatus::Stale { changed } => {
+                for file in changed.iter() {
+                    writeln!(w, "  changed: {}", file)?;
+                }
+            }
+            VerifyStatus::Missing { absent } => {
+                for file in absent.iter() {
+                    writeln!(w, "  absent: {}", file)?;
+                }
+            }
+            VerifyStatus::Ok => {}
+        }
+    }
+    Ok(())
+}
+
+/// Render `verify_all` reports as a machine-readable JSON array for tooling.
+pub fn write_report_json(w: &mut dyn fmt::Write, reports: &[VerifyReport]) -> Result<()> {
+    write!(w, "[")?;
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 {
+            write!(w, ",")?;
+        }
+        write!(w, "{{\"name\":\"{}\",\"status\":\"{}\"",
+            json_escape(&report.name), report.status.label())?;
+        let (key, files) = match &report.status {
+            VerifyStatus::Stale { changed } => ("changed", Some(changed)),
+            VerifyStatus::Missing { absent } => ("absent", Some(absent)),
+            VerifyStatus::Ok => ("changed", None),
+        };
+        if let Some(files) = files {
+            write!(w, ",\"{}\":[", key)?;
+            for (j, file) in files.iter().enumerate() {
+                if j > 0 {
+                    write!(w, ",")?;
+                }
+                write!(w, "\"{}\"", json_escape(file))?;
+            }
+            write!(w, "]")?;
+        }
+        write!(w, "}}")?;
+    }
+    write!(w, "]")?;
+    Ok(())
+}
+
+/// Whether a per-entry outcome should short-circuit its dependents.
+fn is_failure(result: &Result<ReifyResult>) -> bool {
+    matches!(result, Err(_) | Ok(Err(_)))
+}
+
+/// Reify a whole manifest, running independent entries concurrently.
+///
+/// A producer/consumer edge is drawn from an entry that lists a path in its
+/// `files` to any entry that lists the same path in its `required_files`. The
+/// entries are topologically sorted into levels and each level is executed on
+/// a worker pool sized to the available parallelism; an entry whose upstream
+/// producer failed (`ExecFail`/`MissingRequiredFiles`, or an I/O error) is
+/// short-circuited with [`ReifyFail::UpstreamFail`] instead of running against
+/// stale inputs. A cycle in the graph is reported as [`ScheduleError::Cycle`].
+///
+/// Results are returned in the same order as `entries`.
+pub fn reify_all(entries: &[Entry]) -> core::result::Result<Vec<Result<ReifyResult>>, ScheduleError> {
+    let n = entries.len();
+
+    // Map each produced path to the entry that builds it.
+    let mut producer: HashMap<PathBuf, usize> = HashMap::new();
+    for (i, e) in entries.iter().enumerate() {
+        for f in e.files.iter() {
+            producer.insert(abspath(f), i);
+        }
+    }
+
+    // Draw edges from producers to the consumers of their outputs.
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut succs: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, e) in entries.iter().enumerate() {
+        for rf in e.required_files.iter() {
+            if let Some(&p) = producer.get(&abspath(rf)) {
+                if p != i && !preds[i].contains(&p) {
+                    preds[i].push(p);
+                    succs[p].push(i);
+                }
+            }
+        }
+    }
+
+    // Kahn's algorithm, grouping each wave of ready entries into a level.
+    let mut indeg: Vec<usize> = preds.iter().map(Vec::len).collect();
+    let mut ready: Vec<usize> = (0..n).filter(|&i| indeg[i] == 0).collect();
+    let mut levels: Vec<Vec<usize>> = Vec::new();
+    let mut placed = 0;
+    while !ready.is_empty() {
+        placed += ready.len();
+        let mut next = Vec::new();
+        for &u in ready.iter() {
+            for &v in succs[u].iter() {
+                indeg[v] -= 1;
+                if indeg[v] == 0 {
+                    next.push(v);
+                }
+            }
+        }
+        levels.push(ready);
+        ready = next;
+    }
+    if placed < n {
+        let names = (0..n)
+            .filter(|&i| indeg[i] > 0)
+            .map(|i| entries[i].name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(ScheduleError::Cycle(names));
+    }
+
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let results: Vec<Mutex<Option<Result<ReifyResult>>>> =
+        (0..n).map(|_| Mutex::new(None)).collect();
+
+    for level in levels.iter() {
+        let cursor = AtomicUsize::new(0);
+        let level = &level[..];
+        let results = &results;
+        thread::scope(|scope| {
+            for _ in 0..workers.min(level.len()) {
+                scope.spawn(|| {
+                    loop {
+                        let slot = cursor.fetch_add(1, Ordering::Relaxed);
+                        if slot >= level.len() {
+                            break;
+                        }
+                        let idx = level[slot];
+                        // Short-circuit if any producer upstream failed; its
+                        // result is already settled from an earlier level.
+                        let blocked = preds[idx].iter().any(|&p| {
+                            results[p].lock().unwrap().as_ref().map_or(false, is_failure)
+                        });
+                        let result = if blocked {
+                            Ok(Err(ReifyFail::UpstreamFail))
+                        } else {
+                            entries[idx].reify()
+                        };
+                        *results[idx].lock().unwrap() = Some(result);
+                    }
+                });
+            }
+        });
+    }
+
+    Ok(results.into_iter().map(|m| m.into_inner().unwrap().unwrap()).collect())
+}
+
 impl fmt::Display for Entry {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // TODO: Display something more useful
@@ -188,8 +843,16 @@ impl FromYaml for Entry {
             name: yaml["name"].as_str().map(String::from).ok_or(Error::MissingName)?,
             cmd: yaml["cmd"].as_str().map(String::from).ok_or(Error::MissingCmd)?,
             sha: yaml["sha"].as_str().map(String::from),
+            psha: yaml["psha"].as_str().map(String::from),
+            cmd_sha: yaml["cmd_sha"].as_str().map(String::from),
+            hash_mode: match yaml["hash_mode"].as_str() {
+                Some("partial") => HashMode::Partial,
+                _ => HashMode::Full,
+            },
+            algo: yaml["algo"].as_str().and_then(Algo::from_tag).unwrap_or_default(),
             files: str_vec(&yaml["files"]),
             required_files: str_vec(&yaml["required_files"]),
+            mtimes: stamp_vec(&yaml["mtimes"]),
         })
     }
 }